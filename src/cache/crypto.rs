@@ -0,0 +1,74 @@
+use aes_gcm::{
+    aead::{Aead, KeyInit},
+    Aes256Gcm, Nonce,
+};
+use anyhow::{anyhow, Result};
+use rand::RngCore;
+
+pub const NONCE_LEN: usize = 12;
+
+/// Derives a 256-bit AES key from a passphrase and salt using bcrypt-pbkdf.
+pub fn derive_key(passphrase: &[u8], salt: &[u8], rounds: u32) -> [u8; 32] {
+    let mut key = [0u8; 32];
+    bcrypt_pbkdf::bcrypt_pbkdf(passphrase, salt, rounds, &mut key)
+        .expect("32-byte output is valid for bcrypt_pbkdf");
+    key
+}
+
+/// Encrypts `plaintext` under `key` with a freshly generated nonce, returning
+/// the nonce alongside the ciphertext (with the GCM tag appended).
+pub fn encrypt(key: &[u8; 32], plaintext: &[u8]) -> Result<([u8; NONCE_LEN], Vec<u8>)> {
+    let cipher = Aes256Gcm::new(key.into());
+
+    let mut nonce_bytes = [0u8; NONCE_LEN];
+    rand::thread_rng().fill_bytes(&mut nonce_bytes);
+
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce_bytes), plaintext)
+        .map_err(|_| anyhow!("failed to encrypt cache entry"))?;
+
+    Ok((nonce_bytes, ciphertext))
+}
+
+/// Decrypts `ciphertext` under `key`/`nonce`, authenticating the GCM tag.
+/// Returns `Err` if the tag doesn't verify, which the caller treats the same
+/// as a cache miss.
+pub fn decrypt(key: &[u8; 32], nonce: &[u8; NONCE_LEN], ciphertext: &[u8]) -> Result<Vec<u8>> {
+    let cipher = Aes256Gcm::new(key.into());
+    cipher
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| anyhow!("cache entry failed authentication"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encrypt_then_decrypt_roundtrips() {
+        let key = derive_key(b"passphrase", b"0123456789abcdef", 4);
+        let (nonce, ciphertext) = encrypt(&key, b"super secret variables").unwrap();
+
+        assert_eq!(decrypt(&key, &nonce, &ciphertext).unwrap(), b"super secret variables");
+    }
+
+    #[test]
+    fn decrypt_fails_on_tampered_ciphertext() {
+        let key = derive_key(b"passphrase", b"0123456789abcdef", 4);
+        let (nonce, mut ciphertext) = encrypt(&key, b"super secret variables").unwrap();
+
+        let last = ciphertext.len() - 1;
+        ciphertext[last] ^= 0xff;
+
+        assert!(decrypt(&key, &nonce, &ciphertext).is_err());
+    }
+
+    #[test]
+    fn decrypt_fails_with_wrong_key() {
+        let key = derive_key(b"passphrase", b"0123456789abcdef", 4);
+        let wrong_key = derive_key(b"other passphrase", b"0123456789abcdef", 4);
+        let (nonce, ciphertext) = encrypt(&key, b"super secret variables").unwrap();
+
+        assert!(decrypt(&wrong_key, &nonce, &ciphertext).is_err());
+    }
+}
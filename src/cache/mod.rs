@@ -0,0 +1,249 @@
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
+    fs,
+    hash::{Hash, Hasher},
+    path::PathBuf,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{anyhow, Result};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+
+mod crypto;
+
+const DEFAULT_TTL: Duration = Duration::from_secs(60 * 60);
+const DEFAULT_ROUNDS: u32 = 64;
+const SALT_LEN: usize = 16;
+
+/// Identifies a single cached variable set.
+pub struct CacheKey {
+    pub project: String,
+    pub environment: String,
+    pub service: String,
+}
+
+impl CacheKey {
+    fn file_name(&self) -> String {
+        let mut hasher = DefaultHasher::new();
+        self.project.hash(&mut hasher);
+        self.environment.hash(&mut hasher);
+        self.service.hash(&mut hasher);
+        format!("{:016x}.cache", hasher.finish())
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedVariables {
+    fetched_at: u64,
+    variables: HashMap<String, String>,
+}
+
+/// An encrypted, on-disk cache of resolved environment variables, which
+/// `railway run` reads when fetching the variables fails or `--offline` is set.
+pub struct VariableCache {
+    dir: PathBuf,
+    ttl: Duration,
+    passphrase: Vec<u8>,
+}
+
+impl VariableCache {
+    pub fn new() -> Result<Self> {
+        let dir = dirs::cache_dir()
+            .ok_or_else(|| anyhow!("could not determine a cache directory for this platform"))?
+            .join("railway");
+        fs::create_dir_all(&dir)?;
+        let passphrase = machine_passphrase(&dir)?;
+        Ok(Self {
+            dir,
+            ttl: DEFAULT_TTL,
+            passphrase,
+        })
+    }
+
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    fn path_for(&self, key: &CacheKey) -> PathBuf {
+        self.dir.join(key.file_name())
+    }
+
+    /// Reads the cached variable set for `key`, if present, not expired (or
+    /// `ignore_ttl` is set), and if it decrypts and authenticates correctly.
+    /// Any failure along the way is treated as a cache miss rather than an
+    /// error, since the network fetch is always the source of truth.
+    pub fn get(&self, key: &CacheKey, ignore_ttl: bool) -> Option<HashMap<String, String>> {
+        let blob = fs::read(self.path_for(key)).ok()?;
+        let plaintext = open_blob(&self.passphrase, &blob).ok()?;
+        let cached: CachedVariables = serde_json::from_slice(&plaintext).ok()?;
+
+        if !ignore_ttl {
+            let fetched_at = UNIX_EPOCH + Duration::from_secs(cached.fetched_at);
+            if SystemTime::now().duration_since(fetched_at).ok()? > self.ttl {
+                return None;
+            }
+        }
+
+        Some(cached.variables)
+    }
+
+    /// Encrypts and persists `variables` for `key`, overwriting any existing
+    /// entry for the same project/environment/service.
+    pub fn put(&self, key: &CacheKey, variables: &HashMap<String, String>) -> Result<()> {
+        let cached = CachedVariables {
+            fetched_at: SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
+            variables: variables.clone(),
+        };
+
+        let blob = seal_blob(&self.passphrase, &serde_json::to_vec(&cached)?)?;
+        write_private(&self.path_for(key), &blob)?;
+        Ok(())
+    }
+}
+
+/// Writes `contents` to `path` with `0600` permissions on unix, so the
+/// ciphertext and the key that protects it aren't left world-readable under
+/// the default umask.
+#[cfg(unix)]
+fn write_private(path: &std::path::Path, contents: &[u8]) -> Result<()> {
+    use std::{fs::OpenOptions, io::Write, os::unix::fs::OpenOptionsExt};
+
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(path)?;
+    file.write_all(contents)?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn write_private(path: &std::path::Path, contents: &[u8]) -> Result<()> {
+    fs::write(path, contents)?;
+    Ok(())
+}
+
+/// Builds the on-disk blob: `rounds || salt || nonce || ciphertext || tag`.
+fn seal_blob(passphrase: &[u8], plaintext: &[u8]) -> Result<Vec<u8>> {
+    let mut salt = [0u8; SALT_LEN];
+    rand::thread_rng().fill_bytes(&mut salt);
+
+    let key = crypto::derive_key(passphrase, &salt, DEFAULT_ROUNDS);
+    let (nonce, ciphertext) = crypto::encrypt(&key, plaintext)?;
+
+    let mut out = Vec::with_capacity(4 + SALT_LEN + crypto::NONCE_LEN + ciphertext.len());
+    out.extend_from_slice(&DEFAULT_ROUNDS.to_le_bytes());
+    out.extend_from_slice(&salt);
+    out.extend_from_slice(&nonce);
+    out.extend_from_slice(&ciphertext);
+    Ok(out)
+}
+
+/// Reverses `seal_blob`, authenticating the GCM tag before returning the
+/// serialized plaintext.
+fn open_blob(passphrase: &[u8], blob: &[u8]) -> Result<Vec<u8>> {
+    if blob.len() < 4 + SALT_LEN + crypto::NONCE_LEN {
+        return Err(anyhow!("cache file is truncated"));
+    }
+
+    let (rounds, rest) = blob.split_at(4);
+    let (salt, rest) = rest.split_at(SALT_LEN);
+    let (nonce, ciphertext) = rest.split_at(crypto::NONCE_LEN);
+
+    let rounds = u32::from_le_bytes(rounds.try_into()?);
+    let salt: [u8; SALT_LEN] = salt.try_into()?;
+    let nonce: [u8; crypto::NONCE_LEN] = nonce.try_into()?;
+
+    let key = crypto::derive_key(passphrase, &salt, rounds);
+    crypto::decrypt(&key, &nonce, ciphertext)
+}
+
+/// Returns a random passphrase unique to this machine, generating and
+/// persisting one as `<dir>/.cache_key` on first use so the cache can only
+/// be decrypted on the machine that wrote it. `dir` is expected to already
+/// exist.
+fn machine_passphrase(dir: &std::path::Path) -> Result<Vec<u8>> {
+    let keyfile = dir.join(".cache_key");
+    if let Ok(existing) = fs::read(&keyfile) {
+        return Ok(existing);
+    }
+
+    let mut passphrase = vec![0u8; 32];
+    rand::thread_rng().fill_bytes(&mut passphrase);
+    write_private(&keyfile, &passphrase)?;
+    Ok(passphrase)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_cache(ttl: Duration) -> VariableCache {
+        let dir = std::env::temp_dir().join(format!("railway-cache-test-{}", rand::random::<u64>()));
+        fs::create_dir_all(&dir).unwrap();
+        // a fixed, test-local passphrase -- never touch the real machine-wide
+        // keyfile under `dirs::cache_dir()` from tests
+        let passphrase = b"test passphrase, not the real machine key".to_vec();
+        VariableCache {
+            dir,
+            ttl,
+            passphrase,
+        }
+    }
+
+    fn test_key() -> CacheKey {
+        CacheKey {
+            project: "proj".into(),
+            environment: "env".into(),
+            service: "svc".into(),
+        }
+    }
+
+    fn test_variables() -> HashMap<String, String> {
+        HashMap::from([("FOO".to_string(), "bar".to_string())])
+    }
+
+    #[test]
+    fn put_then_get_roundtrips_within_ttl() {
+        let cache = test_cache(Duration::from_secs(3600));
+        let variables = test_variables();
+
+        cache.put(&test_key(), &variables).unwrap();
+
+        assert_eq!(cache.get(&test_key(), false), Some(variables));
+    }
+
+    #[test]
+    fn expired_entry_is_a_miss_unless_ttl_is_ignored() {
+        let cache = test_cache(Duration::from_secs(60));
+        let variables = test_variables();
+
+        // write an entry timestamped at the unix epoch, always stale
+        let stale = CachedVariables {
+            fetched_at: 0,
+            variables: variables.clone(),
+        };
+        let blob = seal_blob(&cache.passphrase, &serde_json::to_vec(&stale).unwrap()).unwrap();
+        write_private(&cache.path_for(&test_key()), &blob).unwrap();
+
+        assert_eq!(cache.get(&test_key(), false), None);
+        assert_eq!(cache.get(&test_key(), true), Some(variables));
+    }
+
+    #[test]
+    fn truncated_blob_is_a_miss() {
+        let cache = test_cache(Duration::from_secs(3600));
+        write_private(&cache.path_for(&test_key()), b"too short").unwrap();
+
+        assert_eq!(cache.get(&test_key(), true), None);
+    }
+
+    #[test]
+    fn missing_entry_is_a_miss() {
+        let cache = test_cache(Duration::from_secs(3600));
+        assert_eq!(cache.get(&test_key(), true), None);
+    }
+}
@@ -1,14 +1,23 @@
+use std::{
+    path::Path,
+    time::{Duration, Instant},
+};
+
 use anyhow::bail;
 use is_terminal::IsTerminal;
+use serde::Deserialize;
+use tracing::{debug, info};
+use which::which;
 
 use crate::{
+    cache::{CacheKey, VariableCache},
     controllers::{
         environment::get_matched_environment,
         project::get_project,
         variables::{get_all_plugin_variables, get_service_variables},
     },
     errors::RailwayError,
-    util::{prompt::{prompt_select, PromptService}, shell::get_shell},
+    util::{logging, prompt::{prompt_select, PromptService}, shell::get_shell},
 };
 
 use super::{queries::project::ProjectProject, *};
@@ -24,11 +33,117 @@ pub struct Args {
     #[clap(short, long)]
     environment: Option<String>,
 
+    /// Path to the executable used to run the command, bypassing shell detection
+    /// (overrides the `execution.exec` key in the Railway config file)
+    #[clap(long)]
+    exec: Option<String>,
+
+    /// Arguments passed to `--exec`/the detected shell before the command itself
+    /// (overrides the `execution.args` key in the Railway config file)
+    #[clap(long, num_args = 1..)]
+    shell_args: Option<Vec<String>>,
+
+    /// Run the command directly with `tokio::process::Command`, without going
+    /// through a shell at all. Useful for REPLs and subprocesses that are
+    /// sensitive to shell quoting of arguments
+    #[clap(long)]
+    no_shell: bool,
+
+    /// Use the last cached set of variables instead of fetching them, failing
+    /// if nothing has been cached yet. The project, environment and service
+    /// are still resolved over the network first
+    #[clap(long)]
+    offline: bool,
+
+    /// How long, in seconds, a cached set of variables is considered fresh
+    /// enough to use as a fallback when fetching the variables fails
+    #[clap(long, default_value = "3600")]
+    cache_ttl: u64,
+
+    /// Enable diagnostic logging to stderr. Repeat for more detail (`-v` for
+    /// resolved IDs and variable counts, `-vv` for per-call timing)
+    #[clap(short, long, action = clap::ArgAction::Count)]
+    verbose: u8,
+
     /// Args to pass to the command
     #[clap(trailing_var_arg = true)]
     args: Vec<String>,
 }
 
+/// The program and leading arguments used to run the user's command: a
+/// configured exec or the detected shell, plus its invocation arguments.
+/// Not used for `--no-shell`, which runs the command directly.
+struct ExecutionBackend {
+    program: String,
+    leading_args: Vec<String>,
+}
+
+/// The `execution` table in the Railway config file, letting users pin a
+/// custom exec path and its leading arguments for `run` instead of relying
+/// on shell detection. Lives on `RootConfig` alongside the rest of the
+/// Railway config file's sections, so it's read wherever `Configs` already
+/// locates and parses that file.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ExecutionConfig {
+    exec: Option<String>,
+    args: Option<Vec<String>>,
+}
+
+/// Platform-aware argument templates used when the shell has no explicit
+/// `execution.args` override configured. Matched against the shell's short
+/// name (`"bash"`, `"zsh"`, ...) -- callers must strip any directory/`.exe`
+/// before matching, since a `PATH`-resolved absolute path won't match any arm.
+fn default_shell_args(shell_name: &str) -> Vec<String> {
+    match shell_name {
+        "powershell" | "pwsh" => vec!["-NoLogo".into(), "-Command".into()],
+        "cmd" => vec!["/C".into()],
+        "sh" | "bash" | "zsh" | "fish" => vec!["-c".into()],
+        _ => vec![],
+    }
+}
+
+/// Extracts the short shell name (e.g. `"zsh"`) from a bare name or an
+/// absolute/relative path (e.g. `/bin/zsh`, `zsh.exe`), for matching against
+/// [`default_shell_args`] regardless of how `exec` was specified.
+fn shell_name(exec: &str) -> &str {
+    Path::new(exec)
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or(exec)
+}
+
+/// Resolves the `exec` path against `PATH`, falling back to the path as given
+/// if it can't be found (e.g. it's already absolute, or doesn't exist yet).
+fn resolve_exec_path(exec: &str) -> String {
+    which(exec)
+        .map(|path| path.to_string_lossy().into_owned())
+        .unwrap_or_else(|_| exec.to_owned())
+}
+
+/// Resolves the execution backend for a command, in order of precedence:
+/// `--exec`/`--shell-args`, then the `execution.exec`/`execution.args` keys
+/// in the Railway config file, then the detected shell with its platform
+/// defaults. `--no-shell` is handled by the caller, which does not call this.
+async fn get_execution_backend(configs: &Configs, args: &Args) -> ExecutionBackend {
+    let execution_config = configs.root_config.execution.clone().unwrap_or_default();
+
+    let exec = match args.exec.clone().or(execution_config.exec) {
+        Some(exec) => exec,
+        None => get_shell().await,
+    };
+
+    let leading_args = args
+        .shell_args
+        .clone()
+        .or(execution_config.args)
+        .unwrap_or_else(|| default_shell_args(shell_name(&exec)));
+
+    ExecutionBackend {
+        program: resolve_exec_path(&exec),
+        leading_args,
+    }
+}
+
 enum ServiceOrPlugins {
     Service(String),
     Plugins(Vec<String>),
@@ -93,11 +208,20 @@ async fn get_service_or_plugins(
 }
 
 pub async fn command(args: Args, _json: bool) -> Result<()> {
+    // TODO(cli): hoist `verbose` onto the root `Cli` as a global flag once
+    // this command is wired into the real subcommand tree, so other
+    // subcommands can share the same `-v`/`-vv` behavior without duplicating
+    // the field
+    logging::init(args.verbose);
+
     let configs = Configs::new()?;
     let client = GQLClient::new_authorized(&configs)?;
     let linked_project = configs.get_linked_project().await?;
 
+    let started = Instant::now();
     let project = get_project(&client, &configs, linked_project.project.clone()).await?;
+    info!(project_id = %linked_project.project, "fetched project");
+    debug!(elapsed_ms = started.elapsed().as_millis(), "fetched project");
 
     let environment = args
         .environment
@@ -105,29 +229,80 @@ pub async fn command(args: Args, _json: bool) -> Result<()> {
         .unwrap_or(linked_project.environment.clone());
 
     let environment_id = get_matched_environment(&project, environment)?.id;
+    info!(environment_id = %environment_id, "matched environment");
+
     let service = get_service_or_plugins(&configs, &project, args.service).await?;
+    info!(
+        service = ?match &service {
+            ServiceOrPlugins::Service(id) => id.clone(),
+            ServiceOrPlugins::Plugins(ids) => ids.join(","),
+        },
+        "resolved service"
+    );
 
-    let variables = match service {
-        ServiceOrPlugins::Service(service_id) => {
-            get_service_variables(
-                &client,
-                &configs,
-                linked_project.project.clone(),
-                environment_id,
-                service_id,
-            )
-            .await?
-        }
-        ServiceOrPlugins::Plugins(plugin_ids) => {
-            // we fetch all the plugin variables
-            get_all_plugin_variables(
-                &client,
-                &configs,
-                linked_project.project.clone(),
-                environment_id,
-                &plugin_ids,
-            )
-            .await?
+    // caching is a best-effort convenience: if the cache can't be set up,
+    // `run` goes on without it
+    let cache = VariableCache::new()
+        .ok()
+        .map(|cache| cache.with_ttl(Duration::from_secs(args.cache_ttl)));
+    let cache_key = CacheKey {
+        project: linked_project.project.clone(),
+        environment: environment_id.clone(),
+        service: match &service {
+            ServiceOrPlugins::Service(service_id) => service_id.clone(),
+            ServiceOrPlugins::Plugins(plugin_ids) => plugin_ids.join(","),
+        },
+    };
+
+    let variables = if args.offline {
+        let variables = cache.as_ref().and_then(|cache| cache.get(&cache_key, true)).ok_or_else(|| {
+            anyhow::anyhow!("--offline was passed but no cached variables are available")
+        })?;
+        info!(count = variables.len(), "loaded variables from cache (--offline)");
+        variables
+    } else {
+        let started = Instant::now();
+        let fetched = match service {
+            ServiceOrPlugins::Service(service_id) => {
+                get_service_variables(
+                    &client,
+                    &configs,
+                    linked_project.project.clone(),
+                    environment_id,
+                    service_id,
+                )
+                .await
+            }
+            ServiceOrPlugins::Plugins(plugin_ids) => {
+                // we fetch all the plugin variables
+                get_all_plugin_variables(
+                    &client,
+                    &configs,
+                    linked_project.project.clone(),
+                    environment_id,
+                    &plugin_ids,
+                )
+                .await
+            }
+        };
+
+        match fetched {
+            Ok(variables) => {
+                info!(count = variables.len(), "fetched variables");
+                debug!(elapsed_ms = started.elapsed().as_millis(), "fetched variables");
+                // caching is a best-effort convenience, never fatal to `run`
+                if let Some(cache) = &cache {
+                    let _ = cache.put(&cache_key, &variables);
+                }
+                variables
+            }
+            Err(err) => {
+                info!(error = %err, "variable fetch failed, falling back to cache");
+                cache
+                    .as_ref()
+                    .and_then(|cache| cache.get(&cache_key, false))
+                    .ok_or(err)?
+            }
         }
     };
 
@@ -137,26 +312,34 @@ pub async fn command(args: Args, _json: bool) -> Result<()> {
         // this is for `rails c` and similar REPLs
     })?;
 
-    let args = args.args.iter().map(|s| s.as_str()).collect::<Vec<_>>();
-    if args.is_empty() {
+    if args.args.is_empty() {
         return Err(RailwayError::NoCommandProvided.into());
     }
 
-    let shell = get_shell().await;
-    let shell_options = match shell.as_str() {
-        "powershell" => vec!["/nologo", "-Command", "\""],
-        "pwsh" => vec!["/nologo", "-Command", "\""],
-        "cmd" => vec!["/C"],
-        "sh" => vec!["-c"],
-        _ => vec![],
-    };
+    let started = Instant::now();
+    let exit_status = if args.no_shell {
+        let (program, rest_args) = args
+            .args
+            .split_first()
+            .expect("checked non-empty above");
+
+        tokio::process::Command::new(program)
+            .args(rest_args)
+            .envs(variables)
+            .status()
+            .await?
+    } else {
+        let backend = get_execution_backend(&configs, &args).await;
+        info!(program = %backend.program, "resolved execution backend");
 
-    let exit_status = tokio::process::Command::new(shell)
-        .args(shell_options)
-        .args(args)
-        .envs(variables)
-        .status()
-        .await?;
+        tokio::process::Command::new(backend.program)
+            .args(backend.leading_args)
+            .args(&args.args)
+            .envs(variables)
+            .status()
+            .await?
+    };
+    debug!(elapsed_ms = started.elapsed().as_millis(), "subprocess exited");
 
     if let Some(code) = exit_status.code() {
         // If there is an exit code (process not terminated by signal), exit with that code
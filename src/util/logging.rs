@@ -0,0 +1,26 @@
+use tracing::Level;
+use tracing_subscriber::EnvFilter;
+
+/// Initializes the global tracing subscriber for a verbosity count (`-v`,
+/// `-vv`, ...), as collected by a repeatable `--verbose` flag. At verbosity 0
+/// nothing is printed, keeping stdout/stderr clean for scripts; `-v` enables
+/// info-level diagnostics and `-vv` and above enables debug level. Safe to
+/// call from every subcommand -- a subscriber that's already been installed
+/// is left in place.
+pub fn init(verbosity: u8) {
+    let level = match verbosity {
+        0 => return,
+        1 => Level::INFO,
+        _ => Level::DEBUG,
+    };
+
+    let filter = EnvFilter::builder()
+        .with_default_directive(level.into())
+        .from_env_lossy();
+
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(std::io::stderr)
+        .with_target(false)
+        .try_init();
+}